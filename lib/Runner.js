@@ -5,23 +5,67 @@ const spawn = require('cross-spawn'),
   readline = require('readline'),
   path = require('path');
 
-function getFirstLine(pathToFile /*: string */) /*: Promise<string> */ {
+// Replace the Elm comments in a line of source with spaces. Block comments
+// nest and can span several lines, so this takes how deeply nested in block
+// comments the line starts, and returns how deeply nested it ends.
+function stripComments(
+  line /*: string */,
+  depth /*: number */
+) /*: { code: string, depth: number } */ {
+  var code = '';
+  var index = 0;
+
+  while (index < line.length) {
+    var pair = line.substr(index, 2);
+
+    if (pair === '{-') {
+      depth++;
+      index += 2;
+    } else if (pair === '-}' && depth > 0) {
+      depth--;
+      index += 2;
+      code += depth === 0 ? ' ' : '';
+    } else if (pair === '--' && depth === 0) {
+      break;
+    } else {
+      code += depth === 0 ? line[index] : '';
+      index++;
+    }
+  }
+
+  return { code: code, depth: depth };
+}
+
+// Get the first line of a file that has code on it, e.g. its module
+// declaration, skipping any comments (like a license header) and blank lines
+// that come before it. Comments are replaced with spaces.
+function getFirstCodeLine(pathToFile /*: string */) /*: Promise<string> */ {
   return new Promise((resolve, reject) => {
     const readable = fs.createReadStream(pathToFile);
     const reader = readline.createInterface({ input: readable });
+    let depth = 0;
     let foundLine = false;
 
     readable.on('error', (err) => reject(err));
 
     reader.on('line', (line) => {
-      foundLine = true;
-      reader.close();
-      readable.close();
-      resolve(line);
+      if (foundLine) {
+        return;
+      }
+
+      const stripped = stripComments(line, depth);
+      depth = stripped.depth;
+
+      if (stripped.code.trim() !== '') {
+        foundLine = true;
+        reader.close();
+        readable.close();
+        resolve(stripped.code);
+      }
     });
     reader.on('close', () => {
       if (!foundLine) {
-        reject(new Error(`File ${pathToFile} is empty!`));
+        reject(new Error(`File ${pathToFile} has no code in it!`));
       }
     });
     reader.on('error', (err) => reject(err));
@@ -47,6 +91,45 @@ function moduleFromFilePath(filePathArg) {
     .reverse();
 }
 
+// Get the module name out of a module declaration line, e.g.
+// 'port module Foo.Bar exposing (..)' ===> 'Foo.Bar'
+// Any leading whitespace is accepted. That covers a byte order mark (\s
+// matches U+FEFF) and comments that getFirstCodeLine has replaced with spaces,
+// as well as a declaration that is simply indented.
+function parseModuleName(line /*: string */) /*: ?string */ {
+  var matches = line.match(/^\s*(?:(?:port|effect)\s+)?module\s+(\S+)/);
+
+  return matches ? matches[1] : null;
+}
+
+// Overlapping globs can resolve to the same file more than once, and so can
+// two spellings of one path on a case-insensitive file system, or a path that
+// goes through a symlink. Use the real path to tell whether two paths are the
+// same file, but return the first path each file was found by: the generated
+// source-directories are not symlink-resolved, so findTests and elm make
+// expect paths under them rather than real paths.
+// (fs.realpathSync.native is only available from node 9.2.)
+function uniqueFilePaths(
+  filePaths /*: Array<string> */,
+  realpath /*: (string) => string */ = fs.realpathSync.native ||
+    fs.realpathSync
+) /*: Array<string> */ {
+  const seen = new Set();
+
+  return filePaths
+    .map((filePath) => path.resolve(filePath))
+    .filter(function (filePath) {
+      const key = realpath(filePath);
+
+      if (seen.has(key)) {
+        return false;
+      }
+
+      seen.add(key);
+      return true;
+    });
+}
+
 function moduleFromTestName(testName) {
   return testName.split('.').reverse();
 }
@@ -123,11 +206,10 @@ function findTests(
 function verifyModules(filePaths) {
   return Promise.all(
     filePaths.map(function (filePath) {
-      return getFirstLine(filePath).then(function (line) {
-        var matches = line.match(/^(?:(?:port|effect)\s+)?module\s+(\S+)\s*/);
+      return getFirstCodeLine(filePath).then(function (line) {
+        var moduleName = parseModuleName(line);
 
-        if (matches) {
-          var moduleName = matches[1];
+        if (moduleName) {
           var testModulePaths = moduleFromTestName(moduleName);
           var modulePath = moduleFromFilePath(filePath);
 
@@ -147,7 +229,7 @@ function verifyModules(filePaths) {
         } else {
           return Promise.reject(
             filePath +
-              ' has an invalid module declaration. Check the first line of the file and make sure it has a valid module declaration there!'
+              ' has an invalid module declaration. Check the module declaration (the first line of code, after any comments) and make sure it is valid!'
           );
         }
       });
@@ -157,5 +239,9 @@ function verifyModules(filePaths) {
 
 module.exports = {
   findTests: findTests,
-  getFirstLine: getFirstLine,
+  getFirstCodeLine: getFirstCodeLine,
+  parseModuleName: parseModuleName,
+  stripComments: stripComments,
+  uniqueFilePaths: uniqueFilePaths,
+  verifyModules: verifyModules,
 };
@@ -5,6 +5,7 @@ var chalk = require('chalk');
 var Install = require('./Install.js');
 var Compile = require('./Compile.js');
 var Generate = require('./Generate.js');
+var Runner = require('./Runner.js');
 var spawn = require('cross-spawn');
 var processTitle = 'elm-test';
 var which = require('which');
@@ -266,7 +267,7 @@ function resolveGlobs(fileGlobs) {
     globs = globifyWithRoot(root, 'test?(s)/**/*.elm');
   }
 
-  return flatMap(globs, resolveFilePath);
+  return Runner.uniqueFilePaths(flatMap(globs, resolveFilePath));
 }
 
 function getGlobsToWatch(elmJson) {
@@ -0,0 +1,5 @@
+effect module EffectModule where
+    { command = MyCmd
+    , subscription = MySub
+    }
+    exposing (..)
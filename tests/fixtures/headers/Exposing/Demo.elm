@@ -0,0 +1,6 @@
+module Exposing.Demo exposing (demo)
+
+
+demo : Int
+demo =
+    1
@@ -0,0 +1,6 @@
+module    ExtraSpaces    exposing (a)
+
+
+a : Int
+a =
+    1
@@ -0,0 +1,8 @@
+module SplitHeader
+    {- pick what to expose -}
+    exposing (bar)
+
+
+bar : Int
+bar =
+    1
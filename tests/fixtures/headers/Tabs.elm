@@ -0,0 +1,11 @@
+module	Tabs	exposing	(	a	,	b	)
+
+
+a : Int
+a =
+    1
+
+
+b : Int
+b =
+    2
@@ -0,0 +1,11 @@
+module Test_1.Fuzz2 exposing (fuzz2, test_1)
+
+
+fuzz2 : Int
+fuzz2 =
+    1
+
+
+test_1 : Int
+test_1 =
+    1
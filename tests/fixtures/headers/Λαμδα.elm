@@ -0,0 +1,6 @@
+module Λαμδα exposing (λ)
+
+
+λ : Int
+λ =
+    1
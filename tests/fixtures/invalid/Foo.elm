@@ -0,0 +1,6 @@
+module Fooexposing (a)
+
+
+a : Int
+a =
+    1
@@ -0,0 +1,11 @@
+module WrongName exposing (..)
+
+import Expect
+import Test exposing (..)
+
+
+suite : Test
+suite =
+    test "this should never run" <|
+        \() ->
+            Expect.pass
@@ -0,0 +1,3 @@
+moduleHelper : Int
+moduleHelper =
+    1
@@ -0,0 +1,12 @@
+{- This file is missing its module declaration. -}
+
+
+import Expect
+import Test exposing (..)
+
+
+suite : Test
+suite =
+    test "this should never run" <|
+        \() ->
+            Expect.pass
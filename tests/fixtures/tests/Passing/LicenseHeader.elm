@@ -0,0 +1,33 @@
+{-
+   Copyright (c) 2020, the elm-test contributors
+   All rights reserved.
+
+   Redistribution and use in source and binary forms, with or without
+   modification, are permitted provided that the following conditions are met:
+
+   * Redistributions of source code must retain the above copyright notice,
+     this list of conditions and the following disclaimer.
+
+   * Redistributions in binary form must reproduce the above copyright notice,
+     this list of conditions and the following disclaimer in the documentation
+     and/or other materials provided with the distribution.
+
+   THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+   AND ANY EXPRESS OR IMPLIED WARRANTIES ARE DISCLAIMED. {- Nested comments
+   are fine too. -} IN NO EVENT SHALL THE COPYRIGHT HOLDER BE LIABLE FOR ANY
+   DAMAGES ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE.
+
+-}
+
+
+module Passing.LicenseHeader exposing (..)
+
+import Expect
+import Test exposing (..)
+
+
+plainExpectation : Test
+plainExpectation =
+    test "this should pass" <|
+        \() ->
+            Expect.equal "success" "success"
@@ -239,6 +239,16 @@ describe('flags', () => {
     }).timeout(60000);
   });
 
+  describe('test file paths', () => {
+    it('Should only pass each test file once when given it twice', () => {
+      const testFile = path.join('tests', 'Passing', 'One.elm');
+      const runResult = execElmTest(['--report=json', testFile, testFile]);
+      const firstOutput = JSON.parse(runResult.stdout.split('\n')[0]);
+
+      assert.deepEqual(firstOutput.paths, [path.resolve(testFile)]);
+    }).timeout(60000);
+  });
+
   describe('--fuzz', () => {
     it('Should default to 100', () => {
       const runResult = execElmTest([
@@ -2,10 +2,13 @@
 
 const Runner = require('../lib/Runner.js');
 const assert = require('assert');
+const fs = require('fs-extra');
+const glob = require('glob');
+const os = require('os');
 const path = require('path');
 
 // TODO: remove this when we drop node 8 support
-function assertThrowsAsync(fn, regExp) {
+function assertThrowsAsync(fn, expected) {
   return fn()
     .then(
       () => () => {},
@@ -13,40 +16,367 @@ function assertThrowsAsync(fn, regExp) {
         throw e;
       }
     )
-    .then((f) => assert.throws(f, regExp));
+    .then((f) =>
+      // Given a string, assert.throws would use it as the failure message
+      // rather than match against it, so check the message here.
+      assert.throws(f, (err) => {
+        const message = err instanceof Error ? err.message : String(err);
+        assert.ok(
+          expected instanceof RegExp
+            ? expected.test(message)
+            : message.includes(expected),
+          `Expected "${message}" to match ${String(expected)}`
+        );
+        return true;
+      })
+    );
 }
 
 describe('Runner', () => {
-  describe('getFirstLine', () => {
+  describe('getFirstCodeLine', () => {
     it('gets first line of this file', () =>
-      Runner.getFirstLine(__filename).then((fl) =>
-        assert.strictEqual(fl, "'use strict';")
+      Runner.getFirstCodeLine(__filename).then((line) =>
+        assert.strictEqual(line, "'use strict';")
       ));
 
     it('gets first line of a dummy file', () =>
-      Runner.getFirstLine(
+      Runner.getFirstCodeLine(
         path.join(__dirname, 'fixtures', 'dummy.txt')
-      ).then((fl) => assert.strictEqual(fl, 'DUMMY')));
+      ).then((line) => assert.strictEqual(line, 'DUMMY')));
 
-    it('gets first empty line of a dummy file', () =>
-      Runner.getFirstLine(
+    it('skips leading empty lines', () =>
+      Runner.getFirstCodeLine(
         path.join(__dirname, 'fixtures', 'empty-line.txt')
-      ).then((fl) => assert.strictEqual(fl, '')));
+      ).then((line) => assert.strictEqual(line, 'dfh')));
+
+    it('gets first line of a file with \\r line endings', () =>
+      Runner.getFirstCodeLine(
+        path.join(__dirname, 'fixtures', 'cr-line-endings.txt')
+      ).then((line) =>
+        assert.strictEqual(line, 'module CarriageReturn exposing (..)')
+      ));
+
+    it('gets the only line of a file without a trailing newline', () =>
+      Runner.getFirstCodeLine(
+        path.join(__dirname, 'fixtures', 'no-trailing-newline.txt')
+      ).then((line) =>
+        assert.strictEqual(line, 'module NoTrailingNewline exposing (foo)')
+      ));
+
+    it('skips a license header block comment', () => {
+      const p = path.join(
+        __dirname,
+        'fixtures',
+        'tests',
+        'Passing',
+        'LicenseHeader.elm'
+      );
+      return Runner.getFirstCodeLine(p).then((line) =>
+        assert.strictEqual(line, 'module Passing.LicenseHeader exposing (..)')
+      );
+    });
+
+    it('skips line comments, blank lines and inline comments', () =>
+      Runner.getFirstCodeLine(
+        path.join(__dirname, 'fixtures', 'comment-before-module.txt')
+      ).then((line) =>
+        assert.strictEqual(line, 'module InlineComment   exposing (..) ')
+      ));
 
     it('fails on an empty file', () => {
       const p = path.join(__dirname, 'fixtures', 'empty');
       return assertThrowsAsync(
-        () => Runner.getFirstLine(p),
-        `File ${p} is empty`
+        () => Runner.getFirstCodeLine(p),
+        `File ${p} has no code in it`
+      );
+    });
+
+    it('fails on a file containing only whitespace', () => {
+      const p = path.join(__dirname, 'fixtures', 'whitespace-only.txt');
+      return assertThrowsAsync(
+        () => Runner.getFirstCodeLine(p),
+        `File ${p} has no code in it`
+      );
+    });
+
+    it('fails on a file containing only a newline', () => {
+      const p = path.join(__dirname, 'fixtures', 'newline-only.txt');
+      return assertThrowsAsync(
+        () => Runner.getFirstCodeLine(p),
+        `File ${p} has no code in it`
       );
     });
 
     it('fails a non existant file', () => {
       const p = path.join(__dirname, 'fixtures', 'must-never-exist');
       return assertThrowsAsync(
-        () => Runner.getFirstLine(p),
+        () => Runner.getFirstCodeLine(p),
         `ENOENT: no such file or directory`
       );
     });
   });
+
+  describe('stripComments', () => {
+    it('replaces block and line comments with spaces', () =>
+      assert.deepStrictEqual(
+        Runner.stripComments('module Foo {- a -} exposing (..) -- b', 0),
+        { code: 'module Foo   exposing (..) ', depth: 0 }
+      ));
+
+    it('leaves a stray -} outside a comment as literal text', () =>
+      assert.deepStrictEqual(
+        Runner.stripComments('exposing ( foo -} , bar )', 0),
+        { code: 'exposing ( foo -} , bar )', depth: 0 }
+      ));
+
+    it('treats {--} as an empty block comment', () =>
+      assert.deepStrictEqual(Runner.stripComments('module{--}Foo', 0), {
+        code: 'module Foo',
+        depth: 0,
+      }));
+
+    it('does not end a block comment at --', () =>
+      assert.deepStrictEqual(
+        Runner.stripComments('{- not -- the end -} module Foo', 0),
+        { code: '  module Foo', depth: 0 }
+      ));
+
+    it('tracks nested block comments across lines', () =>
+      assert.deepStrictEqual(
+        Runner.stripComments('{- outer {- inner -} still outer', 0),
+        { code: '', depth: 1 }
+      ));
+
+    it('resumes code after a block comment from a previous line', () =>
+      assert.deepStrictEqual(Runner.stripComments('done -} module Foo', 1), {
+        code: '  module Foo',
+        depth: 0,
+      }));
+  });
+
+  describe('parseModuleName', () => {
+    it('parses a module declaration', () =>
+      assert.strictEqual(
+        Runner.parseModuleName('module Passing.One exposing (..)'),
+        'Passing.One'
+      ));
+
+    it('parses a port module declaration', () =>
+      assert.strictEqual(
+        Runner.parseModuleName('port module Port1 exposing (..)'),
+        'Port1'
+      ));
+
+    it('parses a module declaration indented with spaces', () =>
+      assert.strictEqual(
+        Runner.parseModuleName('  module Nested.Module.Test exposing (..)'),
+        'Nested.Module.Test'
+      ));
+
+    it('parses a module declaration after a BOM and leading spaces', () =>
+      assert.strictEqual(
+        Runner.parseModuleName('\uFEFF  module Passing.One exposing (..)'),
+        'Passing.One'
+      ));
+
+    it('returns null for a line without a module declaration', () =>
+      assert.strictEqual(Runner.parseModuleName('import Expect'), null));
+  });
+
+  describe('uniqueFilePaths', () => {
+    it('keeps one entry for two casings of the same file', () => {
+      // Simulate a case-insensitive file system.
+      const onDisk = path.resolve('project', 'tests', 'Passing', 'One.elm');
+      const typed = path.resolve('project', 'tests', 'passing', 'one.elm');
+      const realpath = (p) =>
+        p.toLowerCase() === onDisk.toLowerCase() ? onDisk : p;
+
+      const filePaths = Runner.uniqueFilePaths([typed, onDisk], realpath);
+
+      assert.deepStrictEqual(filePaths, [typed]);
+    });
+
+    it('keeps files differing only by case on a case-sensitive system', () => {
+      const upper = path.resolve('project', 'tests', 'Foo.elm');
+      const lower = path.resolve('project', 'tests', 'foo.elm');
+      const filePaths = Runner.uniqueFilePaths([upper, lower], (p) => p);
+
+      assert.deepStrictEqual(filePaths, [upper, lower]);
+    });
+
+    it('keeps one entry for a file also reached through a symlink', () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'elm-test-'));
+
+      try {
+        const testFile = path.join(dir, 'tests', 'Foo.elm');
+        const link = path.join(dir, 'tests', 'link');
+        fs.mkdirSync(path.join(dir, 'tests'));
+        fs.writeFileSync(testFile, 'module Foo exposing (..)\n');
+        // Junctions let Windows create a directory link without admin rights.
+        fs.symlinkSync(path.join(dir, 'tests'), link, 'junction');
+
+        assert.deepStrictEqual(
+          Runner.uniqueFilePaths([testFile, path.join(link, 'Foo.elm')]),
+          [testFile]
+        );
+      } finally {
+        fs.removeSync(dir);
+      }
+    });
+
+    it('returns paths through a symlinked directory unresolved', () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'elm-test-'));
+
+      try {
+        const testFile = path.join(dir, 'project', 'tests', 'Foo.elm');
+        fs.mkdirSync(path.join(dir, 'shared'));
+        fs.mkdirSync(path.join(dir, 'project'));
+        fs.writeFileSync(
+          path.join(dir, 'shared', 'Foo.elm'),
+          'module Foo exposing (..)\n'
+        );
+        fs.symlinkSync(
+          path.join(dir, 'shared'),
+          path.join(dir, 'project', 'tests'),
+          'junction'
+        );
+
+        assert.deepStrictEqual(Runner.uniqueFilePaths([testFile]), [testFile]);
+      } finally {
+        fs.removeSync(dir);
+      }
+    });
+
+    it('drops a file given twice', () => {
+      const p = path.join(__dirname, 'fixtures', 'dummy.txt');
+      assert.strictEqual(Runner.uniqueFilePaths([p, p]).length, 1);
+    });
+  });
+
+  describe('fixture module declarations', () => {
+    function assertModuleNamesMatchPaths(dir) {
+      const files = glob.sync('**/*.elm', { cwd: dir });
+
+      assert.ok(files.length > 0);
+
+      return Promise.all(
+        files.map((file) =>
+          Runner.getFirstCodeLine(path.join(dir, file)).then((line) =>
+            assert.strictEqual(
+              Runner.parseModuleName(line),
+              file.replace(/\.elm$/, '').split('/').join('.')
+            )
+          )
+        )
+      );
+    }
+
+    it('parses the module name of every test fixture', () =>
+      assertModuleNamesMatchPaths(path.join(__dirname, 'fixtures', 'tests')));
+
+    it('parses the module name of the port module fixtures', () =>
+      assertModuleNamesMatchPaths(path.join(__dirname, 'fixtures', 'src')));
+  });
+
+  describe('verifyModules', () => {
+    it('accepts modules whose names match their paths', () => {
+      const dir = path.join(__dirname, 'fixtures', 'tests', 'Passing');
+      return Runner.verifyModules([
+        path.join(dir, 'One.elm'),
+        path.join(dir, 'LicenseHeader.elm'),
+      ]);
+    });
+
+    it('finds no module name in a file without a module declaration', () => {
+      const p = path.join(
+        __dirname,
+        'fixtures',
+        'invalid',
+        'NoModuleDeclaration.elm'
+      );
+      return Runner.getFirstCodeLine(p).then((line) =>
+        assert.strictEqual(Runner.parseModuleName(line), null)
+      );
+    });
+
+    it('rejects a file without a module declaration', () => {
+      const p = path.join(
+        __dirname,
+        'fixtures',
+        'invalid',
+        'NoModuleDeclaration.elm'
+      );
+      return assertThrowsAsync(
+        () => Runner.verifyModules([p]),
+        'has an invalid module declaration'
+      );
+    });
+
+    it('rejects a module whose name does not match its path', () => {
+      const p = path.join(__dirname, 'fixtures', 'invalid', 'Mismatch.elm');
+      return assertThrowsAsync(
+        () => Runner.verifyModules([p]),
+        'has a module declaration of "WrongName"'
+      );
+    });
+
+    it('accepts a module name containing non-ASCII letters', () =>
+      Runner.verifyModules([
+        path.join(__dirname, 'fixtures', 'headers', 'Λαμδα.elm'),
+      ]));
+
+    it('rejects a file that starts with a moduleHelper definition', () => {
+      const p = path.join(__dirname, 'fixtures', 'invalid', 'ModuleHelper.elm');
+      return assertThrowsAsync(
+        () => Runner.verifyModules([p]),
+        'has an invalid module declaration'
+      );
+    });
+
+    it('accepts a module named Exposing.Demo', () =>
+      Runner.verifyModules([
+        path.join(__dirname, 'fixtures', 'headers', 'Exposing', 'Demo.elm'),
+      ]));
+
+    it('accepts a module with exposing after a comment on a later line', () => {
+      const p = path.join(__dirname, 'fixtures', 'headers', 'SplitHeader.elm');
+      return Runner.verifyModules([p])
+        .then(() => Runner.getFirstCodeLine(p))
+        .then((line) =>
+          assert.strictEqual(Runner.parseModuleName(line), 'SplitHeader')
+        );
+    });
+
+    it('accepts a module declaration separated by tabs', () =>
+      Runner.verifyModules([
+        path.join(__dirname, 'fixtures', 'headers', 'Tabs.elm'),
+      ]));
+
+    it('accepts an effect module with a multi-line where record', () => {
+      const p = path.join(__dirname, 'fixtures', 'headers', 'EffectModule.elm');
+      return Runner.verifyModules([p])
+        .then(() => Runner.getFirstCodeLine(p))
+        .then((line) =>
+          assert.strictEqual(Runner.parseModuleName(line), 'EffectModule')
+        );
+    });
+
+    it('accepts extra spaces around the module name', () =>
+      Runner.verifyModules([
+        path.join(__dirname, 'fixtures', 'headers', 'ExtraSpaces.elm'),
+      ]));
+
+    it('accepts a module name containing digits and underscores', () =>
+      Runner.verifyModules([
+        path.join(__dirname, 'fixtures', 'headers', 'Test_1', 'Fuzz2.elm'),
+      ]));
+
+    it('rejects exposing glued to the module name', () => {
+      const p = path.join(__dirname, 'fixtures', 'invalid', 'Foo.elm');
+      return assertThrowsAsync(
+        () => Runner.verifyModules([p]),
+        '"Fooexposing" - which does not match its filename'
+      );
+    });
+  });
 });